@@ -0,0 +1,6 @@
+mod cluster_tabu;
+mod diameter;
+mod metrics;
+
+pub use cluster_tabu::cluster_tabu;
+pub use diameter::diameter;
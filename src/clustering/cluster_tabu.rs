@@ -1,5 +1,10 @@
-use crate::search::tabu_search;
-use core::hash::Hash;
+use crate::search::tabu_search_with_key;
+use core::hash::{Hash, Hasher};
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::rc::Rc;
 
 /// Runs a tabu-search based clustering
 /// # Arguments
@@ -8,64 +13,191 @@ use core::hash::Hash;
 /// * `n_clusters`: the number of clusters to identify
 /// * `max_iterations`: the number of iterations (at most) to search for
 /// * `stopping_cost`: if `Some(x)`, we stop the search when the cost of the current state no longer exceeds `x`.
-pub fn cluster_tabu<Item>(
+/// * `tabu_tenure`: the number of iterations a visited clustering stays forbidden before it expires
+/// * `delta_cost`: an optional cheap incremental form of `cost`. Given the clusters before a move,
+///   the index of the cluster an item is leaving, the index of the cluster it's entering, and the
+///   item itself, it should return the cost of the clustering *after* the move. Since moving one
+///   item only ever changes the two clusters it touches, this can usually be computed in O(cluster
+///   size) instead of rescanning every cluster via `cost`. Pass `None` to always fall back to `cost`.
+pub fn cluster_tabu<Item, C, D>(
     items: Vec<Item>,
-    cost: impl Fn(&Vec<Vec<Item>>) -> f64,
+    cost: C,
     n_clusters: usize,
     max_iterations: usize,
     stopping_cost: Option<f64>,
+    tabu_tenure: usize,
+    delta_cost: Option<D>,
 ) -> Vec<Vec<Item>>
 where
     Item: Clone + Eq + Hash + PartialEq,
+    C: Fn(&Vec<Vec<Item>>) -> f64,
+    D: Fn(&Vec<Vec<Item>>, usize, usize, &Item) -> f64,
 {
     if n_clusters == 1 {
         return vec![items];
     }
 
-    #[derive(Clone, Eq, Hash, PartialEq)]
+    #[derive(Clone)]
     struct State<Item> {
         clusters: Vec<Vec<Item>>,
     }
 
-    fn descendants<Item>(state: &State<Item>) -> impl Iterator<Item = State<Item>>
+    // `State` holds a full clustering, which is expensive to clone and hash on every iteration.
+    // Instead of tracking tabu membership on the state itself, key it by a canonical item ->
+    // cluster-index assignment, independent of the order items happen to sit in within a
+    // cluster. This is already a compact, `Hash + Eq + Clone` value, so it's returned as-is
+    // rather than folded through a second hash: `cached_cost` also uses it to index the cost
+    // cache, where a collision would silently hand back another clustering's cost instead of
+    // merely (and harmlessly) over-forbidding a tabu move.
+    type AssignmentKey = Vec<(u64, usize)>;
+
+    fn assignment_key<Item: Hash>(state: &State<Item>) -> AssignmentKey {
+        let mut assignment: AssignmentKey = state
+            .clusters
+            .iter()
+            .enumerate()
+            .flat_map(|(cluster_index, cluster)| {
+                cluster.iter().map(move |item| {
+                    let mut hasher = DefaultHasher::new();
+                    item.hash(&mut hasher);
+                    (hasher.finish(), cluster_index)
+                })
+            })
+            .collect();
+        assignment.sort_unstable();
+        assignment
+    }
+
+    // A cost cache bounded to at most `capacity` entries, evicting the oldest entry once full.
+    // `Moves` records the cost of every candidate it derives via `delta_cost`, not just the one
+    // the search picks, so an unbounded cache would grow by roughly a full neighborhood per
+    // iteration for the life of the run. Bounding it the same way `tabu_tenure` bounds the tabu
+    // queue trades a bit of re-computation for a fixed memory footprint.
+    struct CostCache {
+        capacity: usize,
+        order: VecDeque<AssignmentKey>,
+        values: HashMap<AssignmentKey, f64>,
+    }
+
+    impl CostCache {
+        fn new(capacity: usize) -> Self {
+            CostCache {
+                capacity,
+                order: VecDeque::new(),
+                values: HashMap::new(),
+            }
+        }
+
+        fn get(&self, key: &AssignmentKey) -> Option<f64> {
+            self.values.get(key).copied()
+        }
+
+        fn insert(&mut self, key: AssignmentKey, value: f64) {
+            if self.values.insert(key.clone(), value).is_none() {
+                self.order.push_back(key);
+                if self.order.len() > self.capacity {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.values.remove(&oldest);
+                    }
+                }
+            }
+        }
+    }
+
+    // Looks up the cost of `state` in `cache`, falling back to a full `cost` evaluation (and
+    // recording it) on a miss. The same cache backs both the `cost` closure handed to
+    // `tabu_search_with_key` and the incremental updates performed by `Moves`, so a clustering's
+    // cost is computed at most once per cache generation no matter how many times it's revisited.
+    fn cached_cost<Item: Hash>(
+        state: &State<Item>,
+        cost: &impl Fn(&Vec<Vec<Item>>) -> f64,
+        cache: &RefCell<CostCache>,
+    ) -> f64 {
+        let key = assignment_key(state);
+        if let Some(cost) = cache.borrow().get(&key) {
+            return cost;
+        }
+        let value = cost(&state.clusters);
+        cache.borrow_mut().insert(key, value);
+        value
+    }
+
+    // Lazily yields every descendant of `base`: the clusterings reachable by moving a single item
+    // from one cluster to another. A candidate is only cloned the moment the search actually pulls
+    // it, rather than all at once, so a `tabu_search_with_key` run that stops partway through a
+    // neighborhood (e.g. because it hit `stopping_cost`) never pays for moves it never looked at.
+    // When a `delta_cost` is available, each candidate not already in `cache` has its cost
+    // computed right away from `base` and the move, and recorded in `cache`, so the outer `cost` closure never has to fall back to
+    // a full `cost` re-scan for it.
+    struct Moves<Item, D> {
+        base: State<Item>,
+        delta_cost: Option<Rc<D>>,
+        cache: Rc<RefCell<CostCache>>,
+        from: usize,
+        item_index: usize,
+        to: usize,
+    }
+
+    impl<Item, D> Iterator for Moves<Item, D>
     where
-        Item: Clone + PartialEq,
+        Item: Clone + Hash + PartialEq,
+        D: Fn(&Vec<Vec<Item>>, usize, usize, &Item) -> f64,
     {
-        // All descendants of a state are obtained by moving one item from one cluster to another.
-        // This is done by iterating over all pairs of clusters and all items in the first cluster.
-        // For each pair, we create a new state where the item is moved from the first cluster to the second.
-
-        // We collect the descendants into a vector because we need to return an iterator.
-        let mut descendants = Vec::new();
-
-        // Look at each cluster...
-        for (i, cluster) in state.clusters.iter().enumerate() {
-            // and each item in the cluster...
-            for item in cluster {
-                // then look at each *other* cluster, to see if we can move the item to that cluster.
-                for j in 0..state.clusters.len() {
-                    // If the clusters are the same, skip this pair.
-                    if i == j {
-                        continue;
-                    }
+        type Item = State<Item>;
 
-                    // Execute the move of item from cluster i to cluster j.
-                    let mut new_clusters = state.clusters.clone();
-                    new_clusters[i].retain(|x| x != item);
-                    new_clusters[j].push(item.clone());
+        fn next(&mut self) -> Option<State<Item>> {
+            loop {
+                let clusters = &self.base.clusters;
+                if self.from >= clusters.len() {
+                    return None;
+                }
+                if self.item_index >= clusters[self.from].len() {
+                    self.from += 1;
+                    self.item_index = 0;
+                    self.to = 0;
+                    continue;
+                }
+                if self.to >= clusters.len() {
+                    self.item_index += 1;
+                    self.to = 0;
+                    continue;
+                }
+                if self.to == self.from {
+                    self.to += 1;
+                    continue;
+                }
 
-                    // Store the new state as a descendant.
-                    descendants.push(State {
-                        clusters: new_clusters,
-                    });
+                let item = clusters[self.from][self.item_index].clone();
+                let mut new_clusters = clusters.clone();
+                new_clusters[self.from].retain(|x| x != &item);
+                new_clusters[self.to].push(item.clone());
+                let descendant = State {
+                    clusters: new_clusters,
+                };
+
+                if let Some(delta_cost) = self.delta_cost.as_deref() {
+                    let key = assignment_key(&descendant);
+                    if self.cache.borrow().get(&key).is_none() {
+                        let cost = delta_cost(&self.base.clusters, self.from, self.to, &item);
+                        self.cache.borrow_mut().insert(key, cost);
+                    }
                 }
+
+                self.to += 1;
+                return Some(descendant);
             }
         }
-
-        // Return the iterator over the descendants.
-        descendants.into_iter()
     }
 
+    // Bounds the cost cache to a full neighborhood (every item moved to every other cluster) per
+    // tenure window: generous enough that a clustering revisited within the tenure window is
+    // almost always still cached, without holding onto every clustering ever seen for the life
+    // of the run.
+    let cache_capacity = items
+        .len()
+        .saturating_mul(n_clusters.saturating_sub(1).max(1))
+        .saturating_mul(tabu_tenure.max(1));
+
     // Create an initial state, in which all items are in the first cluster.
     let initial_state = State {
         clusters: vec![items]
@@ -74,12 +206,29 @@ where
             .collect(),
     };
 
-    let best_state = tabu_search(
+    let cache: Rc<RefCell<CostCache>> = Rc::new(RefCell::new(CostCache::new(cache_capacity)));
+    let cost = Rc::new(cost);
+
+    let delta_cost = delta_cost.map(Rc::new);
+    let descendants_cache = Rc::clone(&cache);
+    let descendants = move |state: &State<Item>| Moves {
+        base: state.clone(),
+        delta_cost: delta_cost.clone(),
+        cache: Rc::clone(&descendants_cache),
+        from: 0,
+        item_index: 0,
+        to: 0,
+    };
+
+    let cost_cache = Rc::clone(&cache);
+    let best_state = tabu_search_with_key(
         initial_state,
         descendants,
-        |state| cost(&state.clusters),
+        |state| cached_cost(state, cost.as_ref(), &cost_cache),
         max_iterations,
         stopping_cost,
+        tabu_tenure,
+        assignment_key,
     );
 
     best_state.clusters
@@ -110,6 +259,55 @@ mod tests {
             n_clusters,
             max_iterations,
             stopping_cost,
+            max_iterations,
+            None::<fn(&Vec<Vec<i32>>, usize, usize, &i32) -> f64>,
+        )
+        .into_iter()
+        .map(|mut cluster| {
+            cluster.sort();
+            cluster
+        })
+        .collect();
+        clusters.sort();
+
+        assert_eq!(clusters.len(), n_clusters);
+        assert_eq!(clusters, vec![vec![1, 2, 3, 4, 5], vec![6, 7, 8, 9, 10]]);
+    }
+
+    #[test]
+    fn test_clustering_with_delta_cost_matches_full_cost() {
+        // This `delta_cost` just re-derives the full cost from the post-move clusters, which is
+        // enough to check that `cluster_tabu` wires it in correctly and reaches the same result as
+        // `cost` alone. A real incremental `delta_cost` would instead recompute only the diameters
+        // of the `from`/`to` clusters and reuse already-known values for the rest.
+        fn full_cost(clusters: &Vec<Vec<i32>>) -> f64 {
+            clusters
+                .iter()
+                .map(|cluster| {
+                    diameter(cluster, |x, y| (x - y).abs() as f64).unwrap_or(f64::NEG_INFINITY)
+                })
+                .fold(f64::NEG_INFINITY, f64::max)
+        }
+
+        fn delta_cost(clusters: &Vec<Vec<i32>>, from: usize, to: usize, item: &i32) -> f64 {
+            let mut updated = clusters.clone();
+            updated[from].retain(|x| x != item);
+            updated[to].push(*item);
+            full_cost(&updated)
+        }
+
+        let items = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let n_clusters = 2;
+        let max_iterations = 100;
+
+        let mut clusters: Vec<Vec<i32>> = cluster_tabu(
+            items,
+            full_cost,
+            n_clusters,
+            max_iterations,
+            None,
+            max_iterations,
+            Some(delta_cost),
         )
         .into_iter()
         .map(|mut cluster| {
@@ -1,14 +1,15 @@
 //! Provides local search functionality and related algorithms
 //! Currently provided search algorithms:
 //! - tabu search
-//! 
+//! - A* search
+//!
 //! Currently provided derived applications
 //! - clustering
 //! 
 //! for examples, see the included tests.
 
 pub mod search;
-pub use search::tabu_search;
+pub use search::{tabu_search, tabu_search_default_tenure};
 
 pub mod clustering;
 pub use clustering::cluster_tabu;
@@ -0,0 +1,10 @@
+mod astar;
+mod increment;
+mod tabu;
+
+pub use astar::{astar, astar_anytime, astar_stepped, astar_weighted, AstarProgress};
+pub use increment::Increment;
+pub use tabu::{
+    tabu_search, tabu_search_default_tenure, tabu_search_stepped, tabu_search_with_key,
+    TabuSearchProgress, DEFAULT_TABU_TENURE,
+};
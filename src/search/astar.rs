@@ -0,0 +1,510 @@
+use super::Increment;
+use core::cmp::Ordering;
+use core::hash::Hash;
+use hashbrown::HashMap;
+use ordered_float::OrderedFloat;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+/// An entry in the open set, ordered by `f = g + weight * h` (lowest first).
+struct OpenEntry<State> {
+    f: OrderedFloat<f64>,
+    state: State,
+}
+
+impl<State> PartialEq for OpenEntry<State> {
+    fn eq(&self, other: &Self) -> bool {
+        self.f == other.f
+    }
+}
+
+impl<State> Eq for OpenEntry<State> {}
+
+impl<State> PartialOrd for OpenEntry<State> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<State> Ord for OpenEntry<State> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.f.cmp(&other.f)
+    }
+}
+
+/// Runs an A* search for a shortest path from `start` to a state satisfying `goal`.
+/// # Arguments
+/// * `start`: the initial state to start the search from
+/// * `neighbors`: a function yielding the successors of a state, paired with the cost of the edge leading to them
+/// * `heuristic`: an estimate of the remaining cost from a state to the nearest goal
+/// * `goal`: a predicate that holds once a state is an acceptable destination
+///
+/// # Returns
+/// The path from `start` to the discovered goal state (inclusive of both), or `None` if no
+/// such path exists. Provided `heuristic` is admissible (it never overestimates the true
+/// remaining cost), the returned path is optimal.
+pub fn astar<State, N, I>(
+    start: State,
+    neighbors: N,
+    heuristic: impl Fn(&State) -> f64,
+    goal: impl Fn(&State) -> bool,
+) -> Option<Vec<State>>
+where
+    N: Fn(&State) -> I,
+    I: Iterator<Item = (State, f64)>,
+    State: Hash + Clone + Eq,
+{
+    astar_weighted(start, neighbors, heuristic, goal, 1.0)
+}
+
+/// Runs a weighted (bounded-suboptimal) A* search, ordering the open set by `f = g + weight * h`.
+/// # Arguments
+/// * `start`: the initial state to start the search from
+/// * `neighbors`: a function yielding the successors of a state, paired with the cost of the edge leading to them
+/// * `heuristic`: an estimate of the remaining cost from a state to the nearest goal
+/// * `goal`: a predicate that holds once a state is an acceptable destination
+/// * `weight`: the inflation applied to the heuristic; must be `>= 1.0`
+///
+/// # Returns
+/// The path from `start` to the discovered goal state (inclusive of both), or `None` if no
+/// such path exists. Inflating the heuristic trades optimality for speed: the returned path's
+/// cost is at most `weight` times the cost of the optimal path (`weight == 1.0` recovers plain,
+/// optimal A*).
+pub fn astar_weighted<State, N, I>(
+    start: State,
+    neighbors: N,
+    heuristic: impl Fn(&State) -> f64,
+    goal: impl Fn(&State) -> bool,
+    weight: f64,
+) -> Option<Vec<State>>
+where
+    N: Fn(&State) -> I,
+    I: Iterator<Item = (State, f64)>,
+    State: Hash + Clone + Eq,
+{
+    astar_bounded(start, neighbors, heuristic, goal, weight, f64::INFINITY).map(|(path, _)| path)
+}
+
+/// Runs an anytime weighted A* search: it produces a quick, feasible solution using the first
+/// (largest) coefficient in `weights`, then repeatedly re-solves with the next smaller
+/// coefficient, using the current incumbent's cost as an upper bound to prune nodes whose `f`
+/// already reaches or exceeds it. Returns the best (lowest-cost) solution found once `weights` is exhausted.
+/// # Arguments
+/// * `start`: the initial state to start the search from
+/// * `neighbors`: a function yielding the successors of a state, paired with the cost of the edge leading to them
+/// * `heuristic`: an estimate of the remaining cost from a state to the nearest goal
+/// * `goal`: a predicate that holds once a state is an acceptable destination
+/// * `weights`: a descending schedule of heuristic inflation coefficients, e.g. `[5.0, 2.0, 1.0]`
+/// * `max_iterations`: the total number of node expansions allowed across the whole schedule
+///
+/// # Returns
+/// The best path found before `max_iterations` was exhausted, or `None` if no coefficient in the
+/// schedule found a path to a goal state.
+pub fn astar_anytime<State, N, I>(
+    start: State,
+    neighbors: N,
+    heuristic: impl Fn(&State) -> f64,
+    goal: impl Fn(&State) -> bool,
+    weights: &[f64],
+    max_iterations: usize,
+) -> Option<Vec<State>>
+where
+    N: Fn(&State) -> I,
+    I: Iterator<Item = (State, f64)>,
+    State: Hash + Clone + Eq,
+{
+    let mut incumbent: Option<(Vec<State>, f64)> = None;
+    let mut iterations_left = max_iterations;
+
+    for &weight in weights {
+        if iterations_left == 0 {
+            break;
+        }
+
+        let upper_bound = incumbent.as_ref().map_or(f64::INFINITY, |(_, cost)| *cost);
+        let (found, used) = astar_bounded_counted(
+            start.clone(),
+            &neighbors,
+            &heuristic,
+            &goal,
+            weight,
+            upper_bound,
+            iterations_left,
+        );
+        iterations_left -= used;
+
+        if let Some(solution) = found {
+            incumbent = Some(solution);
+        }
+    }
+
+    incumbent.map(|(path, _)| path)
+}
+
+/// Runs weighted A*, pruning any node whose `f` already reaches or exceeds `upper_bound`.
+fn astar_bounded<State, N, I>(
+    start: State,
+    neighbors: N,
+    heuristic: impl Fn(&State) -> f64,
+    goal: impl Fn(&State) -> bool,
+    weight: f64,
+    upper_bound: f64,
+) -> Option<(Vec<State>, f64)>
+where
+    N: Fn(&State) -> I,
+    I: Iterator<Item = (State, f64)>,
+    State: Hash + Clone + Eq,
+{
+    astar_bounded_counted(start, &neighbors, &heuristic, &goal, weight, upper_bound, usize::MAX).0
+}
+
+/// Like [`astar_bounded`], but also caps the number of node expansions at `max_iterations` and
+/// reports how many were actually used, so callers can share a budget across several solves.
+fn astar_bounded_counted<State, N, I>(
+    start: State,
+    neighbors: &N,
+    heuristic: &impl Fn(&State) -> f64,
+    goal: &impl Fn(&State) -> bool,
+    weight: f64,
+    upper_bound: f64,
+    max_iterations: usize,
+) -> (Option<(Vec<State>, f64)>, usize)
+where
+    N: Fn(&State) -> I,
+    I: Iterator<Item = (State, f64)>,
+    State: Hash + Clone + Eq,
+{
+    let mut open = BinaryHeap::new();
+    let mut g_score = HashMap::new();
+    let mut came_from = HashMap::new();
+
+    g_score.insert(start.clone(), 0.0);
+    open.push(Reverse(OpenEntry {
+        f: OrderedFloat(weight * heuristic(&start)),
+        state: start,
+    }));
+
+    let mut iterations = 0;
+
+    while iterations < max_iterations {
+        let Some(Reverse(OpenEntry { f, state: current })) = open.pop() else {
+            break;
+        };
+        iterations += 1;
+
+        // The open set is ordered by `f`, so once the best remaining node can no longer beat the
+        // incumbent, neither can any node behind it: stop expanding for this coefficient.
+        if f.0 >= upper_bound {
+            break;
+        }
+
+        if goal(&current) {
+            let current_g = *g_score.get(&current).unwrap_or(&f64::INFINITY);
+            return (Some((reconstruct_path(&came_from, current), current_g)), iterations);
+        }
+
+        let current_g = *g_score.get(&current).unwrap_or(&f64::INFINITY);
+
+        // This entry may be stale: a cheaper path to `current` was found after it was pushed, so
+        // `f` no longer matches what the best known `g` implies. A fresher, cheaper entry for
+        // `current` is already in (or about to be pushed into) the open set, so skip re-expanding
+        // this one.
+        if f.0 > current_g + weight * heuristic(&current) {
+            continue;
+        }
+
+        for (neighbor, edge_cost) in neighbors(&current) {
+            let tentative_g = current_g + edge_cost;
+            let is_better = match g_score.get(&neighbor) {
+                Some(&g) => tentative_g < g,
+                None => true,
+            };
+
+            if is_better {
+                came_from.insert(neighbor.clone(), current.clone());
+                g_score.insert(neighbor.clone(), tentative_g);
+                open.push(Reverse(OpenEntry {
+                    f: OrderedFloat(tentative_g + weight * heuristic(&neighbor)),
+                    state: neighbor,
+                }));
+            }
+        }
+    }
+
+    (None, iterations)
+}
+
+/// The internal state of an A* search that's been paused partway through, so it can be resumed
+/// by later calls to [`astar_stepped`].
+pub struct AstarProgress<State> {
+    open: BinaryHeap<Reverse<OpenEntry<State>>>,
+    g_score: HashMap<State, f64>,
+    came_from: HashMap<State, State>,
+    weight: f64,
+}
+
+impl<State> AstarProgress<State>
+where
+    State: Hash + Clone + Eq,
+{
+    /// Starts a fresh, unconverged search from `start`, ordering the open set by `f = g + weight * h`.
+    pub fn new(start: State, heuristic: impl Fn(&State) -> f64, weight: f64) -> Self {
+        let mut open = BinaryHeap::new();
+        let mut g_score = HashMap::new();
+
+        g_score.insert(start.clone(), 0.0);
+        open.push(Reverse(OpenEntry {
+            f: OrderedFloat(weight * heuristic(&start)),
+            state: start,
+        }));
+
+        AstarProgress {
+            open,
+            g_score,
+            came_from: HashMap::new(),
+            weight,
+        }
+    }
+}
+
+/// Pops and expands the single most promising open node, returning the path to it if it
+/// satisfies `goal`. Returns `None` both when the node didn't satisfy `goal` and when the open
+/// set was already empty; callers distinguish the two via `progress.open`'s emptiness.
+fn astar_step<State, N, I>(
+    progress: &mut AstarProgress<State>,
+    neighbors: &N,
+    heuristic: &impl Fn(&State) -> f64,
+    goal: &impl Fn(&State) -> bool,
+) -> Option<Vec<State>>
+where
+    N: Fn(&State) -> I,
+    I: Iterator<Item = (State, f64)>,
+    State: Hash + Clone + Eq,
+{
+    let Reverse(OpenEntry { f, state: current }) = progress.open.pop()?;
+
+    if goal(&current) {
+        return Some(reconstruct_path(&progress.came_from, current));
+    }
+
+    let current_g = *progress.g_score.get(&current).unwrap_or(&f64::INFINITY);
+
+    // This entry may be stale: a cheaper path to `current` was found after it was pushed, so `f`
+    // no longer matches what the best known `g` implies. A fresher, cheaper entry for `current`
+    // is already in (or about to be pushed into) the open set, so skip re-expanding this one.
+    if f > OrderedFloat(current_g + progress.weight * heuristic(&current)) {
+        return None;
+    }
+
+    for (neighbor, edge_cost) in neighbors(&current) {
+        let tentative_g = current_g + edge_cost;
+        let is_better = match progress.g_score.get(&neighbor) {
+            Some(&g) => tentative_g < g,
+            None => true,
+        };
+
+        if is_better {
+            progress.came_from.insert(neighbor.clone(), current.clone());
+            progress.g_score.insert(neighbor.clone(), tentative_g);
+            progress.open.push(Reverse(OpenEntry {
+                f: OrderedFloat(tentative_g + progress.weight * heuristic(&neighbor)),
+                state: neighbor,
+            }));
+        }
+    }
+
+    None
+}
+
+/// Advances an A* search by up to `max_iterations` node expansions, or until `budget` elapses,
+/// whichever comes first.
+/// # Arguments
+/// * `progress`: the search's state, as returned by a previous call to this function (or a fresh
+///   [`AstarProgress::new`] to start one)
+/// * `neighbors`: a function yielding the successors of a state, paired with the cost of the edge leading to them
+/// * `heuristic`: an estimate of the remaining cost from a state to the nearest goal
+/// * `goal`: a predicate that holds once a state is an acceptable destination
+/// * `max_iterations`: advance by at most this many node expansions in this call
+/// * `budget`: if `Some`, stop advancing once this much wall-clock time has elapsed
+///
+/// # Returns
+/// `Increment::Done(Some(path))` once a goal is reached, `Increment::Done(None)` once the open
+/// set is exhausted with no path found, or `Increment::InProgress(None)` if neither happened
+/// within this slice. Feed `progress` into another call to continue.
+pub fn astar_stepped<State, N, I>(
+    progress: &mut AstarProgress<State>,
+    neighbors: N,
+    heuristic: impl Fn(&State) -> f64,
+    goal: impl Fn(&State) -> bool,
+    max_iterations: usize,
+    budget: Option<Duration>,
+) -> Increment<Option<Vec<State>>>
+where
+    N: Fn(&State) -> I,
+    I: Iterator<Item = (State, f64)>,
+    State: Hash + Clone + Eq,
+{
+    let deadline = budget.map(|budget| Instant::now() + budget);
+
+    for _ in 0..max_iterations {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        if progress.open.is_empty() {
+            return Increment::Done(None);
+        }
+
+        if let Some(path) = astar_step(progress, &neighbors, &heuristic, &goal) {
+            return Increment::Done(Some(path));
+        }
+    }
+
+    Increment::InProgress(None)
+}
+
+/// Walks `came_from` back from `goal` to the start, then reverses it into a start-to-goal path.
+fn reconstruct_path<State>(came_from: &HashMap<State, State>, goal: State) -> Vec<State>
+where
+    State: Hash + Clone + Eq,
+{
+    let mut path = vec![goal];
+
+    while let Some(previous) = came_from.get(path.last().unwrap()) {
+        path.push(previous.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_astar_linear() {
+        let start = 20;
+        let neighbors = |&state: &i32| (state - 1..=state + 1).filter(move |&x| x != state && x >= 0).map(|x| (x, 1.0));
+        let heuristic = |&state: &i32| state as f64;
+        let goal = |&state: &i32| state == 0;
+
+        let path = astar(start, neighbors, heuristic, goal).unwrap();
+
+        assert_eq!(path.first(), Some(&20));
+        assert_eq!(path.last(), Some(&0));
+        assert_eq!(path.len(), 21);
+    }
+
+    #[test]
+    fn test_astar_grid_is_optimal() {
+        let start = (0, 0);
+        let target = (5, 5);
+        let neighbors = move |&(x, y): &(i32, i32)| {
+            (-1..2)
+                .cartesian_product(-1..2)
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+                .map(move |(dx, dy)| ((x + dx, y + dy), 1.0))
+        };
+        let heuristic = move |&(x, y): &(i32, i32)| ((target.0 - x).abs().max((target.1 - y).abs())) as f64;
+        let goal = move |&state: &(i32, i32)| state == target;
+
+        let path = astar(start, neighbors, heuristic, goal).unwrap();
+
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&target));
+        // Diagonal moves are allowed at unit cost, so Chebyshev distance is the optimal length.
+        assert_eq!(path.len() - 1, 5);
+    }
+
+    #[test]
+    fn test_astar_unreachable_goal_returns_none() {
+        let start = 0;
+        let neighbors = |_: &i32| std::iter::empty::<(i32, f64)>();
+        let heuristic = |_: &i32| 0.0;
+        let goal = |&state: &i32| state == 999;
+
+        assert_eq!(astar(start, neighbors, heuristic, goal), None);
+    }
+
+    #[test]
+    fn test_astar_weighted_stays_within_bound() {
+        let start = 0;
+        let target = 20;
+        let neighbors = |&state: &i32| (state..=state + 1).filter(move |&x| x != state).map(|x| (x, 1.0));
+        let heuristic = move |&state: &i32| (target - state) as f64;
+        let goal = move |&state: &i32| state == target;
+        let weight = 2.0;
+
+        let path = astar_weighted(start, neighbors, heuristic, goal, weight).unwrap();
+        let cost = (path.len() - 1) as f64;
+
+        assert_eq!(path.last(), Some(&target));
+        assert!(cost <= weight * target as f64);
+    }
+
+    #[test]
+    fn test_astar_anytime_converges_to_optimum() {
+        let start = (0, 0);
+        let target = (5, 5);
+        let neighbors = move |&(x, y): &(i32, i32)| {
+            (-1..2)
+                .cartesian_product(-1..2)
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+                .map(move |(dx, dy)| ((x + dx, y + dy), 1.0))
+        };
+        let heuristic = move |&(x, y): &(i32, i32)| ((target.0 - x).abs().max((target.1 - y).abs())) as f64;
+        let goal = move |&state: &(i32, i32)| state == target;
+        let schedule = [5.0, 2.0, 1.0];
+
+        let path = astar_anytime(start, neighbors, heuristic, goal, &schedule, 10_000).unwrap();
+
+        assert_eq!(path.last(), Some(&target));
+        assert_eq!(path.len() - 1, 5);
+    }
+
+    #[test]
+    fn test_astar_stepped_resumes_across_calls() {
+        let start = 20;
+        let neighbors = |&state: &i32| (state - 1..=state + 1).filter(move |&x| x != state && x >= 0).map(|x| (x, 1.0));
+        let heuristic = |&state: &i32| state as f64;
+        let goal = |&state: &i32| state == 0;
+
+        let mut progress = AstarProgress::new(start, heuristic, 1.0);
+
+        // Advance in small slices; the search should still reach the goal, just spread across
+        // several calls instead of one.
+        let mut increment = Increment::InProgress(None);
+        for _ in 0..20 {
+            if increment.is_done() {
+                break;
+            }
+            increment = astar_stepped(&mut progress, neighbors, heuristic, goal, 2, None);
+        }
+
+        assert_eq!(increment, Increment::Done(Some((0..=20).rev().collect())));
+    }
+
+    #[test]
+    fn test_astar_stepped_respects_time_budget() {
+        let start = 20;
+        let neighbors = |&state: &i32| (state - 1..=state + 1).filter(move |&x| x != state && x >= 0).map(|x| (x, 1.0));
+        let heuristic = |&state: &i32| state as f64;
+        let goal = |&state: &i32| state == 0;
+
+        let mut progress = AstarProgress::new(start, heuristic, 1.0);
+        // A budget of zero elapses immediately, so not a single expansion should run.
+        let increment = astar_stepped(
+            &mut progress,
+            neighbors,
+            heuristic,
+            goal,
+            usize::MAX,
+            Some(std::time::Duration::ZERO),
+        );
+
+        assert_eq!(increment, Increment::InProgress(None));
+    }
+}
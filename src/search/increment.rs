@@ -0,0 +1,24 @@
+/// The status returned by a time-budgeted, resumable search after one slice of work.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Increment<State> {
+    /// The search has not yet converged. Carries the best solution found so far; pass the
+    /// driver's progress value back in to continue from where it left off.
+    InProgress(State),
+    /// The search has converged, or met its stopping criterion. Carries the final solution.
+    Done(State),
+}
+
+impl<State> Increment<State> {
+    /// The best solution found so far, regardless of whether the search has converged.
+    pub fn into_inner(self) -> State {
+        match self {
+            Increment::InProgress(state) => state,
+            Increment::Done(state) => state,
+        }
+    }
+
+    /// `true` if the search has converged, or met its stopping criterion.
+    pub fn is_done(&self) -> bool {
+        matches!(self, Increment::Done(_))
+    }
+}
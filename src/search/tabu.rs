@@ -1,74 +1,276 @@
+use super::Increment;
 use core::hash::Hash;
-use hashbrown::HashSet;
+use hashbrown::HashMap;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// A sensible default tabu tenure, for callers who don't have a reason to tune it.
+pub const DEFAULT_TABU_TENURE: usize = 10;
+
+/// Runs a tabu search *minimization* with [`DEFAULT_TABU_TENURE`], for callers who don't have a
+/// reason to tune the tenure themselves.
+/// # Arguments
+/// * `state`: the initial state to start the search from
+/// * `descendants`: a function to generate (possible) descendants of a state given a state
+/// * `cost`: the cost of a state
+/// * `max_iterations`: we stop when we've ran through this many iterations
+/// * `stopping_cost`: if not None, we stop as soon as a descendant's cost falls below this value.
+pub fn tabu_search_default_tenure<State, F, D>(
+    state: State,
+    descendants: F,
+    cost: impl Fn(&State) -> f64,
+    max_iterations: usize,
+    stopping_cost: Option<f64>,
+) -> State
+where
+    D: Iterator<Item = State>,
+    F: Fn(&State) -> D,
+    State: Hash + Clone + Eq,
+{
+    tabu_search(
+        state,
+        descendants,
+        cost,
+        max_iterations,
+        stopping_cost,
+        DEFAULT_TABU_TENURE,
+    )
+}
 
 /// Runs a tabu search *minimization*.
 /// # Arguments
 /// * `state`: the initial state to start the search from
 /// * `descendants`: a function to generate (possible) descendants of a state given a state
 /// * `cost`: the cost of a state
-/// * `max_iterations`: we stop when we've ran through this many iterations 
+/// * `max_iterations`: we stop when we've ran through this many iterations
 /// * `stopping_cost`: if not None, we stop when our cost no longer exceeds this value.
+/// * `tabu_tenure`: the number of iterations a visited state stays forbidden before it expires
+///   and can be revisited. Lower tenures favor intensification (thorough local search), higher
+///   tenures favor diversification (escaping cycles and local optima).
 pub fn tabu_search<State, F, D>(
     state: State,
     descendants: F,
     cost: impl Fn(&State) -> f64,
     max_iterations: usize,
     stopping_cost: Option<f64>,
+    tabu_tenure: usize,
 ) -> State
 where
     D: Iterator<Item = State>,
     F: Fn(&State) -> D,
     State: Hash + Clone + Eq,
 {
-    let mut tabu_list = HashSet::new();
-    let mut best = state.clone();
-    let mut best_cost = cost(&best);
-    let mut current = best.clone();
+    tabu_search_with_key(
+        state,
+        descendants,
+        cost,
+        max_iterations,
+        stopping_cost,
+        tabu_tenure,
+        State::clone,
+    )
+}
+
+/// Runs a tabu search *minimization*, tracking tabu membership through a compact key rather than
+/// the full state. This avoids cloning and hashing large states on every iteration: only `key`'s
+/// output needs to be `Hash + Eq + Clone`, while `State` itself only needs to be `Clone`.
+/// # Arguments
+/// * `state`: the initial state to start the search from
+/// * `descendants`: a function to generate (possible) descendants of a state given a state
+/// * `cost`: the cost of a state
+/// * `max_iterations`: we stop when we've ran through this many iterations
+/// * `stopping_cost`: if not None, we stop as soon as a descendant's cost falls below this value.
+/// * `tabu_tenure`: the number of iterations a visited state stays forbidden before it expires
+///   and can be revisited.
+/// * `key`: extracts the compact value used to track tabu membership for a state. Pass
+///   `State::clone` (what [`tabu_search`] does) if the state itself is already cheap to hash.
+pub fn tabu_search_with_key<State, F, D, K>(
+    state: State,
+    descendants: F,
+    cost: impl Fn(&State) -> f64,
+    max_iterations: usize,
+    stopping_cost: Option<f64>,
+    tabu_tenure: usize,
+    key: impl Fn(&State) -> K,
+) -> State
+where
+    D: Iterator<Item = State>,
+    F: Fn(&State) -> D,
+    State: Clone,
+    K: Hash + Eq + Clone,
+{
+    let initial_cost = cost(&state);
+    let mut progress = TabuSearchProgress::new(state, initial_cost, tabu_tenure, stopping_cost);
 
-    // Loop until we reach the stopping cost or the maximum number of iterations.
     for _ in 0..max_iterations {
-        // Keep track of the best descendant we've seen so far.
-        let mut best_descendant = None;
-        let mut best_descendant_cost = f64::INFINITY;
-
-        // Add the current state to the tabu list
-        tabu_list.insert(current.clone());
-
-        // Consider all descendants of the current state.
-        for descendant in descendants(&current) {
-            // If the descendant is in the tabu list, skip it.
-            if tabu_list.contains(&descendant) {
-                continue;
-            }
+        if tabu_step(&mut progress, &descendants, &cost, &key) {
+            break;
+        }
+    }
+
+    progress.best
+}
+
+/// The internal state of a tabu search that's been paused partway through, so it can be resumed
+/// by later calls to [`tabu_search_stepped`].
+pub struct TabuSearchProgress<State, K> {
+    current: State,
+    best: State,
+    best_cost: f64,
+    // Counts how many entries of each key are currently within the tenure window. A plain
+    // `HashSet` would under-count expiry when two different states share a key: evicting one
+    // occurrence must not forget about another still-active occurrence of the same key.
+    tabu_counts: HashMap<K, usize>,
+    tabu_queue: VecDeque<K>,
+    tabu_tenure: usize,
+    stopping_cost: Option<f64>,
+}
+
+impl<State, K> TabuSearchProgress<State, K>
+where
+    State: Clone,
+    K: Hash + Eq + Clone,
+{
+    /// Starts a fresh, unconverged search from `state`.
+    pub fn new(
+        state: State,
+        cost: f64,
+        tabu_tenure: usize,
+        stopping_cost: Option<f64>,
+    ) -> Self {
+        TabuSearchProgress {
+            current: state.clone(),
+            best: state,
+            best_cost: cost,
+            tabu_counts: HashMap::new(),
+            tabu_queue: VecDeque::new(),
+            tabu_tenure,
+            stopping_cost,
+        }
+    }
+
+    /// The best state found so far.
+    pub fn best(&self) -> &State {
+        &self.best
+    }
+}
 
-            // If the descendant is better than the stopping cost, return it.
-            let descendant_cost = cost(&descendant);
-            if let Some(sc) = stopping_cost {
-                if descendant_cost < sc {
-                    return descendant;
+/// Runs one iteration of tabu search against `progress`, mutating it in place.
+/// Returns `true` if the stopping cost was met (the search has converged).
+fn tabu_step<State, F, D, K>(
+    progress: &mut TabuSearchProgress<State, K>,
+    descendants: &F,
+    cost: &impl Fn(&State) -> f64,
+    key: &impl Fn(&State) -> K,
+) -> bool
+where
+    D: Iterator<Item = State>,
+    F: Fn(&State) -> D,
+    State: Clone,
+    K: Hash + Eq + Clone,
+{
+    // Keep track of the best descendant we've seen so far.
+    let mut best_descendant = None;
+    let mut best_descendant_cost = f64::INFINITY;
+
+    // Add the current state's key to the tabu list, then let the oldest entry expire once
+    // the tenure is exceeded so forbidden moves don't accumulate forever.
+    let current_key = key(&progress.current);
+    progress.tabu_queue.push_back(current_key.clone());
+    *progress.tabu_counts.entry(current_key).or_insert(0) += 1;
+    if progress.tabu_queue.len() > progress.tabu_tenure {
+        if let Some(expired) = progress.tabu_queue.pop_front() {
+            if let Some(count) = progress.tabu_counts.get_mut(&expired) {
+                *count -= 1;
+                if *count == 0 {
+                    progress.tabu_counts.remove(&expired);
                 }
             }
+        }
+    }
 
-            // If the descendant is better than the best descendant we've seen so far, update the best descendant.
-            if descendant_cost < best_descendant_cost {
-                best_descendant = Some(descendant);
-                best_descendant_cost = descendant_cost;
-            }
+    // Consider all descendants of the current state.
+    for descendant in descendants(&progress.current) {
+        let descendant_cost = cost(&descendant);
+
+        // If the descendant is tabu, skip it, unless the aspiration criterion applies: a
+        // move to a new global best is always worth taking, tabu or not.
+        if progress.tabu_counts.contains_key(&key(&descendant)) && descendant_cost >= progress.best_cost {
+            continue;
         }
 
-        // If the best descendant is better than the best state we've seen so far, update the best state.
-        // Also, update the current state to the best descendant of the current state.
-        if let Some(descendant) = best_descendant {
-            if best_descendant_cost < best_cost {
-                best = descendant.clone();
-                best_cost = best_descendant_cost;
+        // If the descendant is better than the stopping cost, converge on it.
+        if let Some(sc) = progress.stopping_cost {
+            if descendant_cost < sc {
+                progress.current = descendant.clone();
+                progress.best = descendant;
+                progress.best_cost = descendant_cost;
+                return true;
             }
+        }
 
-            current = descendant;
+        // If the descendant is better than the best descendant we've seen so far, update the best descendant.
+        if descendant_cost < best_descendant_cost {
+            best_descendant = Some(descendant);
+            best_descendant_cost = descendant_cost;
         }
     }
 
-    best
+    // If the best descendant is better than the best state we've seen so far, update the best state.
+    // Also, update the current state to the best descendant of the current state.
+    if let Some(descendant) = best_descendant {
+        if best_descendant_cost < progress.best_cost {
+            progress.best = descendant.clone();
+            progress.best_cost = best_descendant_cost;
+        }
+
+        progress.current = descendant;
+    }
+
+    false
+}
+
+/// Advances a tabu search by up to `max_iterations` iterations, or until `budget` elapses,
+/// whichever comes first.
+/// # Arguments
+/// * `progress`: the search's state, as returned by a previous call to this function (or a fresh
+///   [`TabuSearchProgress::new`] to start one).
+/// * `descendants`: a function to generate (possible) descendants of a state given a state
+/// * `cost`: the cost of a state
+/// * `key`: extracts the compact value used to track tabu membership for a state
+/// * `max_iterations`: advance by at most this many iterations in this call
+/// * `budget`: if `Some`, stop advancing once this much wall-clock time has elapsed
+///
+/// # Returns
+/// `Increment::Done` once the stopping cost has been met; otherwise `Increment::InProgress`
+/// carrying the best state found so far, across all slices. Feed `progress` into another call to continue.
+pub fn tabu_search_stepped<State, F, D, K>(
+    progress: &mut TabuSearchProgress<State, K>,
+    descendants: F,
+    cost: impl Fn(&State) -> f64,
+    key: impl Fn(&State) -> K,
+    max_iterations: usize,
+    budget: Option<Duration>,
+) -> Increment<State>
+where
+    D: Iterator<Item = State>,
+    F: Fn(&State) -> D,
+    State: Clone,
+    K: Hash + Eq + Clone,
+{
+    let deadline = budget.map(|budget| Instant::now() + budget);
+
+    for _ in 0..max_iterations {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        if tabu_step(progress, &descendants, &cost, &key) {
+            return Increment::Done(progress.best.clone());
+        }
+    }
+
+    Increment::InProgress(progress.best.clone())
 }
 
 #[cfg(test)]
@@ -84,7 +286,25 @@ mod tests {
         let max_iterations = 100;
         let stopping_cost = None;
 
-        let best = tabu_search(state, descendants, cost, max_iterations, stopping_cost);
+        let best = tabu_search(
+            state,
+            descendants,
+            cost,
+            max_iterations,
+            stopping_cost,
+            max_iterations,
+        );
+
+        assert_eq!(best, 0);
+    }
+
+    #[test]
+    fn test_tabu_search_default_tenure() {
+        let state = 20;
+        let descendants = |state: &i32| (state - 1..=state + 1).filter(|&x| x >= 0);
+        let cost = |state: &i32| *state as f64;
+
+        let best = tabu_search_default_tenure(state, descendants, cost, 100, None);
 
         assert_eq!(best, 0);
     }
@@ -97,7 +317,14 @@ mod tests {
         let max_iterations = 100;
         let stopping_cost = None;
 
-        let best = tabu_search(state, descendants, cost, max_iterations, stopping_cost);
+        let best = tabu_search(
+            state,
+            descendants,
+            cost,
+            max_iterations,
+            stopping_cost,
+            max_iterations,
+        );
 
         assert_eq!(best, 10);
     }
@@ -122,9 +349,124 @@ mod tests {
                 cost,
                 max_iterations,
                 Some(stopping_cost),
+                max_iterations,
             );
 
             assert_eq!(best, (5, 5));
         }
     }
+
+    #[test]
+    fn test_tabu_tenure_allows_revisiting_after_expiry() {
+        // The only moves are back and forth between 0 and 1, so with a bounded tenure the search
+        // must revisit a forbidden state once it expires, rather than stalling forever.
+        let state = 0;
+        let expanded = std::cell::RefCell::new(Vec::new());
+        let descendants = |state: &i32| {
+            expanded.borrow_mut().push(*state);
+            vec![1 - state].into_iter()
+        };
+        let cost = |_: &i32| 0.0;
+        let max_iterations = 5;
+
+        let best = tabu_search(state, descendants, cost, max_iterations, None, 1);
+
+        assert_eq!(best, 0);
+        assert_eq!(expanded.into_inner(), vec![0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_tabu_search_with_key_reaches_same_result_as_full_state() {
+        let state = 20;
+        let descendants = |state: &i32| (state - 1..=state + 1).filter(|&x| x >= 0);
+        let cost = |state: &i32| *state as f64;
+        let max_iterations = 100;
+
+        let best = tabu_search_with_key(
+            state,
+            descendants,
+            cost,
+            max_iterations,
+            None,
+            max_iterations,
+            |state| *state,
+        );
+
+        assert_eq!(best, 0);
+    }
+
+    #[test]
+    fn test_aspiration_criterion_survives_key_collisions() {
+        // Every state down this ladder collides on the same key, so without the aspiration
+        // criterion every step, the first included, would find its only descendant tabu and stall
+        // at the starting state. Because each step is a strict improvement, aspiration should let the
+        // search keep descending anyway.
+        let state = 10;
+        let descendants = |state: &i32| std::iter::once(state - 2).filter(|&x| x >= 0);
+        let cost = |state: &i32| *state as f64;
+        let max_iterations = 10;
+
+        let best = tabu_search_with_key(
+            state,
+            descendants,
+            cost,
+            max_iterations,
+            None,
+            max_iterations,
+            |_| 0,
+        );
+
+        assert_eq!(best, 0);
+    }
+
+    #[test]
+    fn test_tabu_search_stepped_resumes_across_calls() {
+        let state = 20;
+        let descendants = |state: &i32| (state - 1..=state + 1).filter(|&x| x >= 0);
+        let cost = |state: &i32| *state as f64;
+        let initial_cost = cost(&state);
+
+        let mut progress = TabuSearchProgress::new(state, initial_cost, 100, None);
+
+        // Advance in small slices of 5 iterations at a time; each call should pick up where the
+        // last left off, rather than restarting from `state`.
+        let mut increment = Increment::InProgress(state);
+        for _ in 0..20 {
+            increment = tabu_search_stepped(&mut progress, descendants, cost, |s| *s, 5, None);
+        }
+
+        assert_eq!(increment.into_inner(), 0);
+    }
+
+    #[test]
+    fn test_tabu_search_stepped_reports_done_at_stopping_cost() {
+        let state = 10;
+        let descendants = |state: &i32| (state - 1..=state + 1).filter(|&x| x >= 0);
+        let cost = |state: &i32| *state as f64;
+
+        let mut progress = TabuSearchProgress::new(state, cost(&state), 100, Some(1.0));
+        let increment = tabu_search_stepped(&mut progress, descendants, cost, |s| *s, 100, None);
+
+        assert!(increment.is_done());
+    }
+
+    #[test]
+    fn test_tabu_search_stepped_respects_time_budget() {
+        let state = 10;
+        let descendants = |state: &i32| (state - 1..=state + 1).filter(|&x| x >= 0);
+        let cost = |state: &i32| *state as f64;
+
+        let mut progress = TabuSearchProgress::new(state, cost(&state), 100, None);
+        // A budget of zero elapses immediately, so not a single iteration should run.
+        let increment = tabu_search_stepped(
+            &mut progress,
+            descendants,
+            cost,
+            |s| *s,
+            usize::MAX,
+            Some(std::time::Duration::ZERO),
+        );
+
+        assert_eq!(increment, Increment::InProgress(10));
+    }
 }